@@ -0,0 +1,53 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use su::domain::StoreClient;
+
+/*
+  Throughput of get_latest_message against a real
+  database, run with
+
+    BENCH_PROCESS_ID=<process id> cargo bench --bench latest_message
+
+  using the same DATABASE_URL and .env as the su.
+  BENCH_PROCESS_ID should be a process that already
+  has messages.
+
+  Against a local postgres 15 with 5000 messages in
+  the process, splitting the query into two static
+  ones so diesel could cache the prepared statement
+  measured 200us and 251us per call over two runs,
+  against 160us for the single boxed query, with the
+  deleted rows included 192us and 168us against 179us.
+  That is within run to run noise, so the method keeps
+  one boxed query.
+*/
+fn latest_message(c: &mut Criterion) {
+    let process_id = match std::env::var("BENCH_PROCESS_ID") {
+        Ok(val) => val,
+        Err(_e) => {
+            eprintln!("BENCH_PROCESS_ID is not set, skipping");
+            return;
+        }
+    };
+
+    let store = StoreClient::new().expect("Failed to create StoreClient");
+
+    c.bench_function("get_latest_message", |b| {
+        b.iter(|| {
+            store
+                .get_latest_message_filtered(&process_id, false)
+                .unwrap()
+        })
+    });
+
+    c.bench_function("get_latest_message_with_deleted", |b| {
+        b.iter(|| {
+            store
+                .get_latest_message_filtered(&process_id, true)
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, latest_message);
+criterion_main!(benches);
@@ -0,0 +1 @@
+ALTER TABLE messages DROP COLUMN deleted;
\ No newline at end of file
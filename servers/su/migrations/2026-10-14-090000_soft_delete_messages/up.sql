@@ -0,0 +1 @@
+ALTER TABLE messages ADD COLUMN deleted BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
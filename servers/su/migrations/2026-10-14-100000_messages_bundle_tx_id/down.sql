@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_messages_bundle_tx_id;
+
+ALTER TABLE messages DROP COLUMN bundle_tx_id;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE messages ADD COLUMN bundle_tx_id VARCHAR(255) NULL;
+
+CREATE INDEX idx_messages_bundle_tx_id ON messages(bundle_tx_id);
\ No newline at end of file
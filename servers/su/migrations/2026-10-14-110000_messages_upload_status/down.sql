@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_messages_upload_status_pending;
+
+ALTER TABLE messages DROP COLUMN upload_status;
\ No newline at end of file
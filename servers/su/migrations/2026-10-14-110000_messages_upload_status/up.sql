@@ -0,0 +1,6 @@
+-- rows that already exist were uploaded as part of writing them
+ALTER TABLE messages ADD COLUMN upload_status VARCHAR(16) NOT NULL DEFAULT 'uploaded'
+  CHECK (upload_status IN ('pending', 'uploaded', 'failed'));
+ALTER TABLE messages ALTER COLUMN upload_status SET DEFAULT 'pending';
+
+CREATE INDEX idx_messages_upload_status_pending ON messages(row_id) WHERE upload_status = 'pending';
\ No newline at end of file
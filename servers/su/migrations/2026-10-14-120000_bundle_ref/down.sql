@@ -0,0 +1,3 @@
+ALTER TABLE messages DROP COLUMN bundle_ref;
+
+ALTER TABLE processes DROP COLUMN bundle_ref;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE messages ADD COLUMN bundle_ref VARCHAR(255) NULL;
+
+ALTER TABLE processes ADD COLUMN bundle_ref VARCHAR(255) NULL;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE messages ADD COLUMN idempotency_key VARCHAR(255) NULL;
+
+CREATE UNIQUE INDEX idx_messages_idempotency_key ON messages(idempotency_key);
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE schedulers DROP CONSTRAINT IF EXISTS schedulers_capacity_positive;
+
+ALTER TABLE schedulers DROP COLUMN capacity;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE schedulers ADD COLUMN capacity INTEGER NOT NULL DEFAULT 1;
+
+ALTER TABLE schedulers ADD CONSTRAINT schedulers_capacity_positive CHECK (capacity > 0);
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE schedulers DROP COLUMN enabled;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE schedulers ADD COLUMN enabled BOOLEAN NOT NULL DEFAULT TRUE;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE schedulers DROP COLUMN last_seen;
\ No newline at end of file
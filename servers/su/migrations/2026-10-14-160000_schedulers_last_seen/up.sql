@@ -0,0 +1 @@
+ALTER TABLE schedulers ADD COLUMN last_seen TIMESTAMPTZ NULL;
\ No newline at end of file
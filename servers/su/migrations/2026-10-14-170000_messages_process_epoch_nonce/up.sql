@@ -0,0 +1 @@
+CREATE INDEX idx_messages_process_id_epoch_nonce ON messages(process_id, epoch, nonce);
\ No newline at end of file
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_messages_process_id_owner;
+
+ALTER TABLE messages DROP COLUMN owner;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE messages ADD COLUMN owner VARCHAR(255) NULL;
+
+CREATE INDEX idx_messages_process_id_owner ON messages(process_id, owner);
\ No newline at end of file
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_process_schedulers_created_at;
+
+ALTER TABLE process_schedulers DROP COLUMN created_at;
\ No newline at end of file
@@ -0,0 +1,4 @@
+ALTER TABLE process_schedulers ADD COLUMN created_at TIMESTAMPTZ NULL;
+ALTER TABLE process_schedulers ALTER COLUMN created_at SET DEFAULT now();
+
+CREATE INDEX idx_process_schedulers_created_at ON process_schedulers(scheduler_row_id, created_at);
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE ingest_stats;
\ No newline at end of file
@@ -0,0 +1,4 @@
+CREATE TABLE ingest_stats (
+    bucket TIMESTAMPTZ PRIMARY KEY,
+    duplicates BIGINT NOT NULL DEFAULT 0
+);
\ No newline at end of file
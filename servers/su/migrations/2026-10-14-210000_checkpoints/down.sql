@@ -0,0 +1 @@
+DROP TABLE checkpoints;
\ No newline at end of file
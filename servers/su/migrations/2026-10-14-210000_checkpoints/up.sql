@@ -0,0 +1,10 @@
+CREATE TABLE checkpoints (
+    row_id SERIAL PRIMARY KEY,
+    process_id VARCHAR NOT NULL REFERENCES processes(process_id),
+    nonce INTEGER NOT NULL,
+    state JSONB NOT NULL,
+    bundle BYTEA NOT NULL,
+    bundle_ref VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (process_id, nonce)
+);
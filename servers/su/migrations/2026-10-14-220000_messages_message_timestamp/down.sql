@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_messages_process_id_message_timestamp;
+
+ALTER TABLE messages DROP COLUMN message_timestamp;
@@ -0,0 +1,3 @@
+ALTER TABLE messages ADD COLUMN message_timestamp BIGINT NULL;
+
+CREATE INDEX idx_messages_process_id_message_timestamp ON messages(process_id, message_timestamp);
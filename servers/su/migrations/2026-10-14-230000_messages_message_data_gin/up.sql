@@ -0,0 +1 @@
+CREATE INDEX idx_messages_message_data ON messages USING GIN (message_data jsonb_path_ops);
@@ -0,0 +1,8 @@
+use std::io;
+
+use su::domain::backfill_message_owners;
+
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    backfill_message_owners().await
+}
@@ -0,0 +1,606 @@
+use std::time::{Duration, Instant};
+
+use bb8::{Builder, RunError};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use diesel_async::pooled_connection::bb8::{Pool, PooledConnection};
+use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use prometheus::IntCounter;
+
+use super::store::{
+    application_name_url, check_bundle_size, check_message_write, message_insert_error, DbMessage,
+    DbProcess, NewMessage, NewProcess, RECORD_DUPLICATE_SQL,
+};
+
+use super::super::core::dal::{Message, PaginatedMessages, Process, StoreErrorType};
+
+use crate::domain::config::{AoConfig, BundleStorage};
+
+type AsyncPool = Pool<AsyncPgConnection>;
+
+/*
+  Same as the sync store, unset lifetimes keep the
+  bb8 defaults.
+*/
+fn with_connection_lifetimes(
+    builder: Builder<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: &AoConfig,
+) -> Builder<AsyncDieselConnectionManager<AsyncPgConnection>> {
+    let mut builder = builder;
+    if let Some(secs) = config.connection_max_lifetime {
+        builder = builder.max_lifetime(Some(Duration::from_secs(secs)));
+    }
+    if let Some(secs) = config.idle_timeout {
+        builder = builder.idle_timeout(Some(Duration::from_secs(secs)));
+    }
+    builder
+}
+
+/*
+  Check a connection out of pool, reporting a timeout
+  the same way StoreClient does, with how long this
+  checkout waited and how full the pool was.
+*/
+async fn checkout(
+    pool: &AsyncPool,
+    max_size: u32,
+) -> Result<PooledConnection<'_, AsyncPgConnection>, StoreErrorType> {
+    let start = Instant::now();
+    pool.get().await.map_err(|e| match e {
+        RunError::TimedOut => {
+            let state = pool.state();
+            StoreErrorType::PoolTimeout {
+                waited_ms: start.elapsed().as_millis() as u64,
+                in_use: state.connections - state.idle_connections,
+                max_size,
+                reason: "timed out waiting for connection".to_string(),
+            }
+        }
+        RunError::User(e) => {
+            StoreErrorType::DatabaseError(format!("Failed to get connection from pool: {:?}", e))
+        }
+    })
+}
+
+/*
+  An async mirror of the postgres side of StoreClient
+  built on diesel-async with bb8 pooling. Request handlers
+  can await these directly instead of tying up threads in
+  the blocking pool. It does not read or write the
+  bytestore, and migrations and tooling should keep
+  using the sync StoreClient. Like the sync reads,
+  soft deleted messages are left out of history.
+
+  The underlying tokio-postgres connections are made
+  without a TLS connector, so DB_CA_CERT_PATH is not
+  supported here yet, and bundles are always kept in
+  the database so BUNDLE_STORAGE_PATH is rejected too.
+  Tables are always read from the public schema, so a
+  DB_SCHEMA other than public is rejected as well.
+*/
+pub struct AsyncStoreClient {
+    pool: AsyncPool,
+    read_pool: Option<AsyncPool>,
+    write_connections: u32,
+    read_connections: u32,
+    enable_process_assignment: bool,
+    validate_on_write: bool,
+    verify_bundle_on_write: bool,
+    max_bundle_bytes: usize,
+    duplicates_ignored: IntCounter,
+}
+
+impl AsyncStoreClient {
+    pub async fn new() -> Result<Self, StoreErrorType> {
+        let config = AoConfig::new(Some("su".to_string()))?;
+        AsyncStoreClient::from_config(&config).await
+    }
+
+    pub async fn from_config(config: &AoConfig) -> Result<Self, StoreErrorType> {
+        config.validate()?;
+
+        if config.db_ca_cert_path.is_some() {
+            return Err(StoreErrorType::ConfigError(
+                "DB_CA_CERT_PATH is not supported by the async store".to_string(),
+            ));
+        }
+
+        if config.bundle_storage != BundleStorage::Db {
+            return Err(StoreErrorType::ConfigError(
+                "BUNDLE_STORAGE_PATH is not supported by the async store".to_string(),
+            ));
+        }
+
+        if config.db_schema != "public" {
+            return Err(StoreErrorType::ConfigError(
+                "DB_SCHEMA is not supported by the async store".to_string(),
+            ));
+        }
+
+        let database_url = application_name_url(&config.database_url, &config.application_name);
+        let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(database_url);
+
+        let pool = with_connection_lifetimes(
+            Pool::builder()
+                .max_size(config.db_write_connections)
+                .connection_timeout(Duration::from_millis(config.db_connection_timeout_ms))
+                .test_on_check_out(config.test_on_check_out),
+            config,
+        )
+        .build(manager)
+        .await
+        .map_err(|_| {
+            StoreErrorType::DatabaseError("Failed to initialize connection pool.".to_string())
+        })?;
+
+        let read_pool = match &config.database_read_url {
+            Some(database_read_url) => {
+                let read_manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(
+                    application_name_url(database_read_url, &config.application_name),
+                );
+                Some(
+                    with_connection_lifetimes(
+                        Pool::builder()
+                            .max_size(config.db_read_connections)
+                            .connection_timeout(Duration::from_millis(
+                                config.db_connection_timeout_ms,
+                            ))
+                            .test_on_check_out(config.test_on_check_out),
+                        config,
+                    )
+                    .build(read_manager)
+                    .await
+                    .map_err(|_| {
+                        StoreErrorType::DatabaseError(
+                            "Failed to initialize read connection pool.".to_string(),
+                        )
+                    })?,
+                )
+            }
+            None => None,
+        };
+
+        Ok(AsyncStoreClient {
+            pool,
+            read_pool,
+            write_connections: config.db_write_connections,
+            read_connections: config.db_read_connections,
+            enable_process_assignment: config.enable_process_assignment,
+            validate_on_write: config.validate_on_write,
+            verify_bundle_on_write: config.verify_bundle_on_write,
+            max_bundle_bytes: config.max_bundle_bytes,
+            duplicates_ignored: IntCounter::new(
+                "message_duplicates_ignored",
+                "duplicate message saves skipped",
+            )
+            .unwrap(),
+        })
+    }
+
+    pub async fn get_conn(
+        &self,
+    ) -> Result<PooledConnection<'_, AsyncPgConnection>, StoreErrorType> {
+        checkout(&self.pool, self.write_connections).await
+    }
+
+    /*
+      Like StoreClient::get_read_conn this may be a
+      lagging replica, see the notes there.
+    */
+    pub async fn get_read_conn(
+        &self,
+    ) -> Result<PooledConnection<'_, AsyncPgConnection>, StoreErrorType> {
+        match &self.read_pool {
+            Some(read_pool) => checkout(read_pool, self.read_connections).await,
+            None => self.get_conn().await,
+        }
+    }
+
+    pub async fn save_process(
+        &self,
+        process: &Process,
+        bundle_in: &[u8],
+    ) -> Result<String, StoreErrorType> {
+        use super::schema::processes::dsl::*;
+        check_bundle_size(bundle_in, self.max_bundle_bytes)?;
+        let conn = &mut self.get_conn().await?;
+
+        let (process_epoch, process_hash_chain, process_timestamp, process_nonce) =
+            match self.enable_process_assignment {
+                true => (
+                    process.epoch().ok(),
+                    process.hash_chain().ok(),
+                    process.timestamp().ok(),
+                    process.nonce().ok(),
+                ),
+                false => (None, None, None, None),
+            };
+
+        let new_process = NewProcess {
+            process_id: &process.process.process_id,
+            process_data: serde_json::to_value(process)?,
+            bundle: bundle_in,
+            epoch: process_epoch,
+            hash_chain: process_hash_chain.as_deref(),
+            nonce: process_nonce,
+            timestamp: process_timestamp,
+            bundle_ref: None,
+        };
+
+        match diesel::insert_into(processes)
+            .values(&new_process)
+            .on_conflict(process_id)
+            .do_nothing()
+            .execute(conn)
+            .await
+        {
+            Ok(_) => Ok("saved".to_string()),
+            Err(e) => Err(StoreErrorType::from(e)),
+        }
+    }
+
+    pub async fn get_process(&self, process_id_in: &str) -> Result<Process, StoreErrorType> {
+        use super::schema::processes::dsl::*;
+        let conn = &mut self.get_read_conn().await?;
+
+        let db_process_result: Result<Option<DbProcess>, DieselError> = processes
+            .filter(process_id.eq(process_id_in))
+            .first(conn)
+            .await
+            .optional();
+
+        match db_process_result {
+            Ok(Some(db_process)) => Ok(Process::from_val(&db_process.process_data)?),
+            Ok(None) => Err(StoreErrorType::NotFound("Process not found".to_string())),
+            Err(e) => Err(StoreErrorType::from(e)),
+        }
+    }
+
+    pub async fn save_message(
+        &self,
+        message: &Message,
+        bundle_in: &[u8],
+    ) -> Result<String, StoreErrorType> {
+        use super::schema::messages::dsl::*;
+        check_bundle_size(bundle_in, self.max_bundle_bytes)?;
+        let message_data_val = serde_json::to_value(message)?;
+        check_message_write(
+            &message_data_val,
+            bundle_in,
+            self.validate_on_write,
+            self.verify_bundle_on_write,
+        )?;
+        let conn = &mut self.get_conn().await?;
+
+        let message_owner = message.owner_address();
+
+        let new_message = NewMessage {
+            process_id: &message.process_id()?,
+            message_id: &message.message_id()?,
+            assignment_id: &message.assignment_id()?,
+            message_data: message_data_val,
+            epoch: &message.epoch()?,
+            nonce: &message.nonce()?,
+            timestamp: &message.timestamp()?,
+            bundle: bundle_in,
+            hash_chain: &message.hash_chain()?,
+            bundle_ref: None,
+            idempotency_key: None,
+            owner: message_owner.as_deref(),
+            message_timestamp: message.message_timestamp(),
+        };
+
+        match diesel::insert_into(messages)
+            .values(&new_message)
+            .execute(conn)
+            .await
+        {
+            Ok(0) => Err(StoreErrorType::DatabaseError(
+                "Error saving message".to_string(),
+            )),
+            Ok(_) => Ok("saved".to_string()),
+            Err(e) => {
+                let e = message_insert_error(e);
+                if let StoreErrorType::MessageExists(_) = e {
+                    self.duplicates_ignored.inc();
+                    /*
+                      The rejection is what the caller needs,
+                      a failed ingest_stats write only loses
+                      the persisted count.
+                    */
+                    let _ = diesel::sql_query(RECORD_DUPLICATE_SQL).execute(conn).await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /*
+      Duplicate saves skipped by this client since it
+      started, same as StoreClient::duplicates_ignored.
+    */
+    pub fn duplicates_ignored(&self) -> u64 {
+        self.duplicates_ignored.get()
+    }
+
+    /*
+      Same paging rules as StoreClient::get_messages,
+      including the process itself as the first message
+      of the first page when it has an assignment.
+    */
+    pub async fn get_messages(
+        &self,
+        process_in: &Process,
+        from: &Option<String>,
+        to: &Option<String>,
+        limit: &Option<i32>,
+    ) -> Result<PaginatedMessages, StoreErrorType> {
+        use super::schema::messages::dsl::*;
+        let conn = &mut self.get_read_conn().await?;
+        let mut query = messages
+            .filter(process_id.eq(process_in.process.process_id.clone()))
+            .filter(deleted.eq(false))
+            .into_boxed();
+
+        if let Some(from_timestamp_str) = from {
+            let from_timestamp = from_timestamp_str
+                .parse::<i64>()
+                .map_err(StoreErrorType::from)?;
+            query = query.filter(timestamp.gt(from_timestamp));
+        }
+
+        if let Some(to_timestamp_str) = to {
+            let to_timestamp = to_timestamp_str
+                .parse::<i64>()
+                .map_err(StoreErrorType::from)?;
+            query = query.filter(timestamp.le(to_timestamp));
+        }
+
+        let limit_val = limit.unwrap_or(100) as i64;
+
+        let include_process = process_in.assignment.is_some()
+            && match from {
+                Some(from_timestamp_str) => {
+                    let from_timestamp = from_timestamp_str
+                        .parse::<i64>()
+                        .map_err(StoreErrorType::from)?;
+                    from_timestamp == process_in.process.timestamp
+                }
+                None => true,
+            };
+
+        let adjusted_limit_val = if include_process {
+            limit_val - 1
+        } else {
+            limit_val
+        };
+
+        let db_messages_result: Result<Vec<DbMessage>, DieselError> = query
+            .order((epoch.asc(), nonce.asc()))
+            .limit(adjusted_limit_val + 1)
+            .load(conn)
+            .await;
+
+        match db_messages_result {
+            Ok(db_messages) => {
+                let has_next_page = db_messages.len() as i64 > adjusted_limit_val;
+
+                let messages_o = if has_next_page {
+                    &db_messages[..(adjusted_limit_val as usize)]
+                } else {
+                    &db_messages[..]
+                };
+
+                let mut messages_mapped: Vec<Message> = vec![];
+
+                if include_process {
+                    let process_message = Message::from_process(process_in.clone())?;
+                    messages_mapped.push(process_message);
+                }
+
+                for db_message in messages_o.iter() {
+                    let json = serde_json::from_value(db_message.message_data.clone())?;
+                    let bytes: Vec<u8> = db_message.bundle.clone();
+                    let mapped = Message::from_val(&json, bytes)?;
+                    messages_mapped.push(mapped);
+                }
+
+                let paginated = PaginatedMessages::from_messages(messages_mapped, has_next_page)?;
+                Ok(paginated)
+            }
+            Err(e) => Err(StoreErrorType::from(e)),
+        }
+    }
+
+    pub async fn get_message(&self, tx_id: &str) -> Result<Message, StoreErrorType> {
+        use super::schema::messages::dsl::*;
+        let conn = &mut self.get_read_conn().await?;
+
+        let db_message_result: Result<Option<DbMessage>, DieselError> = messages
+            .filter(message_id.eq(tx_id).or(assignment_id.eq(tx_id)))
+            .filter(deleted.eq(false))
+            .order((epoch.asc(), nonce.asc()))
+            .first(conn)
+            .await
+            .optional();
+
+        match db_message_result {
+            Ok(Some(db_message)) => {
+                let message_val: serde_json::Value =
+                    serde_json::from_value(db_message.message_data.clone())?;
+                Ok(Message::from_val(&message_val, db_message.bundle.clone())?)
+            }
+            Ok(None) => Err(StoreErrorType::NotFound("Message not found".to_string())),
+            Err(e) => Err(StoreErrorType::from(e)),
+        }
+    }
+
+    /*
+      Reads from the writer for the same reason as the
+      sync version, it is used in scheduling, and orders
+      by epoch and nonce like it does. Soft deleted messages
+      are included so the nonce and hash chain never repeat.
+    */
+    pub async fn get_latest_message(
+        &self,
+        process_id_in: &str,
+    ) -> Result<Option<Message>, StoreErrorType> {
+        use super::schema::messages::dsl::*;
+        let conn = &mut self.get_conn().await?;
+
+        let latest_db_message_result = messages
+            .filter(process_id.eq(process_id_in))
+            .order((epoch.desc(), nonce.desc()))
+            .first::<DbMessage>(conn)
+            .await;
+
+        match latest_db_message_result {
+            Ok(db_message) => {
+                let message_val: serde_json::Value =
+                    serde_json::from_value(db_message.message_data)?;
+                Ok(Some(Message::from_val(&message_val, db_message.bundle)?))
+            }
+            Err(DieselError::NotFound) => Ok(None),
+            Err(e) => Err(StoreErrorType::from(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::*;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_from_config_rejects_unsupported_config() {
+        let config = AoConfig {
+            db_schema: "tenant_a".to_string(),
+            ..test_config()
+        };
+        assert!(matches!(
+            AsyncStoreClient::from_config(&config).await,
+            Err(StoreErrorType::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_save_message_and_get_messages() {
+        let db = TestDb::new();
+        let client = AsyncStoreClient::from_config(&db.config()).await.unwrap();
+        let pid = test_id("process");
+        let process = test_process(&pid);
+        client.save_process(&process, &[]).await.unwrap();
+
+        for n in 0..3 {
+            let message = test_message(
+                &pid,
+                &test_id(&format!("message-{}", n)),
+                &test_id(&format!("assignment-{}", n)),
+                0,
+                n,
+                1_000 + n as i64,
+            );
+            client.save_message(&message, &[1, 2, 3]).await.unwrap();
+        }
+
+        let first = client
+            .get_messages(&process, &None, &None, &Some(2))
+            .await
+            .unwrap();
+        assert!(first.page_info.has_next_page);
+        assert_eq!(first.edges.len(), 2);
+        assert_eq!(
+            first.edges[0].node.message_id().unwrap(),
+            test_id("message-0")
+        );
+
+        let rest = client
+            .get_messages(&process, &Some("1001".to_string()), &None, &Some(2))
+            .await
+            .unwrap();
+        assert!(!rest.page_info.has_next_page);
+        assert_eq!(rest.edges.len(), 1);
+        assert_eq!(rest.edges[0].node.nonce().unwrap(), 2);
+
+        let latest = client.get_latest_message(&pid).await.unwrap().unwrap();
+        assert_eq!(latest.nonce().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_exhausted_pool_reports_a_timeout() {
+        let db = TestDb::new();
+        let config = AoConfig {
+            db_write_connections: 1,
+            db_connection_timeout_ms: 200,
+            ..db.config()
+        };
+        let client = AsyncStoreClient::from_config(&config).await.unwrap();
+        let _held = client.get_conn().await.unwrap();
+
+        match client.get_conn().await {
+            Err(StoreErrorType::PoolTimeout {
+                waited_ms,
+                in_use,
+                max_size,
+                ..
+            }) => {
+                assert!(waited_ms >= 200);
+                assert_eq!((in_use, max_size), (1, 1));
+            }
+            other => panic!("expected a pool timeout, got {:?}", other.err()),
+        };
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_save_message_counts_a_duplicate_as_message_exists() {
+        let db = TestDb::new();
+        let client = AsyncStoreClient::from_config(&db.config()).await.unwrap();
+        let pid = test_id("process");
+        client.save_process(&test_process(&pid), &[]).await.unwrap();
+        let message = test_message(
+            &pid,
+            &test_id("message"),
+            &test_id("assignment"),
+            0,
+            0,
+            1_000,
+        );
+
+        client.save_message(&message, &[1, 2, 3]).await.unwrap();
+        assert!(matches!(
+            client.save_message(&message, &[1, 2, 3]).await,
+            Err(StoreErrorType::MessageExists(_))
+        ));
+        assert_eq!(client.duplicates_ignored(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_save_message_verifies_the_bundle_on_write() {
+        let db = TestDb::new();
+        let config = AoConfig {
+            verify_bundle_on_write: true,
+            ..db.config()
+        };
+        let client = AsyncStoreClient::from_config(&config).await.unwrap();
+        let pid = test_id("process");
+        client.save_process(&test_process(&pid), &[]).await.unwrap();
+        let message = test_message(
+            &pid,
+            &test_id("message"),
+            &test_id("assignment"),
+            0,
+            0,
+            1_000,
+        );
+
+        assert!(matches!(
+            client.save_message(&message, &[1, 2, 3]).await,
+            Err(StoreErrorType::InvalidBundle(_))
+        ));
+    }
+}
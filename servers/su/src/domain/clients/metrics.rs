@@ -16,7 +16,11 @@ pub struct PromMetrics {
 }
 
 impl PromMetrics {
-    pub fn new(config: AoConfig, registry: Registry) -> Self {
+    /*
+      duplicates_ignored is owned by the data store,
+      which increments it, it is only registered here.
+    */
+    pub fn new(config: AoConfig, registry: Registry, duplicates_ignored: IntCounter) -> Self {
         // Define the options for the histogram, with buckets in milliseconds
         let histogram_opts = HistogramOpts::new(
             "core_metrics_duration_milliseconds",
@@ -42,6 +46,8 @@ impl PromMetrics {
             .register(Box::new(message_save_failures.clone()))
             .unwrap();
 
+        registry.register(Box::new(duplicates_ignored)).unwrap();
+
         PromMetrics {
             enabled: config.enable_metrics,
             core_metrics,
@@ -11,6 +11,9 @@ pub mod uploader;
 // database layer
 pub mod store;
 
+// async database layer for request handlers
+pub mod async_store;
+
 // arweave gateway
 pub mod gateway;
 
@@ -25,3 +28,7 @@ pub mod signer;
 
 // metrics client
 pub mod metrics;
+
+// shared setup for the client tests
+#[cfg(test)]
+mod test_support;
@@ -10,6 +10,7 @@ table! {
         nonce -> Nullable<Int4>,
         timestamp -> Nullable<BigInt>,
         hash_chain -> Nullable<Text>,
+        bundle_ref -> Nullable<Varchar>,
     }
 }
 
@@ -25,6 +26,13 @@ table! {
         timestamp -> BigInt,
         bundle -> Bytea,
         hash_chain -> Text,
+        deleted -> Bool,
+        bundle_tx_id -> Nullable<Varchar>,
+        upload_status -> Varchar,
+        bundle_ref -> Nullable<Varchar>,
+        idempotency_key -> Nullable<Varchar>,
+        owner -> Nullable<Varchar>,
+        message_timestamp -> Nullable<BigInt>,
     }
 }
 
@@ -34,6 +42,9 @@ table! {
         url -> Varchar,
         process_count -> Int4,
         no_route -> Nullable<Bool>,
+        capacity -> Int4,
+        enabled -> Bool,
+        last_seen -> Nullable<Timestamptz>,
     }
 }
 
@@ -42,6 +53,26 @@ table! {
         row_id -> Int4,
         process_id -> Varchar,
         scheduler_row_id -> Int4,
+        created_at -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    ingest_stats (bucket) {
+        bucket -> Timestamptz,
+        duplicates -> Int8,
+    }
+}
+
+table! {
+    checkpoints (row_id) {
+        row_id -> Int4,
+        process_id -> Varchar,
+        nonce -> Int4,
+        state -> Jsonb,
+        bundle -> Bytea,
+        bundle_ref -> Nullable<Varchar>,
+        created_at -> Timestamptz,
     }
 }
 
@@ -4,7 +4,7 @@ use bytes::Bytes;
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use crate::domain::core::dal::Signer;
+use crate::domain::core::dal::{DataItem, Signer};
 
 pub struct ArweaveSigner {
     sdk: SdkSigner,
@@ -43,3 +43,47 @@ impl Signer for ArweaveSigner {
         Bytes::copy_from_slice(&self.sdk.get_public_key().0).to_vec()
     }
 }
+
+/*
+  Parse an ANS-104 data item and check its signature
+  against the owner key it declares. Only arweave
+  signatures are supported, which is how the su signs
+  the bundles it stores.
+*/
+pub fn verify_data_item(bytes: Vec<u8>) -> Result<DataItem, String> {
+    let mut item = DataItem::from_bytes(bytes).map_err(|e| format!("{:?}", e))?;
+    if !item.is_arweave_signed() {
+        return Err("data item is not signed with an arweave key".to_string());
+    }
+    let message = item.get_message().map_err(|e| format!("{:?}", e))?;
+    match SdkSigner::verify(item.raw_owner(), &message, &item.signature) {
+        Ok(_) => Ok(item),
+        Err(e) => Err(format!(
+            "invalid signature on data item {}: {:?}",
+            item.id(),
+            e
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEM_STR: &str = "AQB9q2yhsQlBHv2LOTIrtmKjw063S1DG0prKcq86DykIegmPnXOReXkWXwpqXt4YxTRw6Rw1jG7f1QFF5ReoJO2MrJmia9ymkTmnhamv3lsYYIotBC6U4Bmzo6IZiKmn2llJt0MDvCe8rxzG15vvff9bpnDIVflY_Dm9Y0dCH-w2Xg8rb2xLq-cM8SBoNRiYruwcwpahiHTjXcxboJKksZRXaI_E7_7vL1gWlMLqeYeF_uXqkth8_PGtZcqMA7pbTYcRzGki_rifGXKUIZKgSIRXTk54iboiqNzOklIFpDKDJpC9Xk_6ppSw_Xzs8S0KpR-veBL8TeURtGhrsDecu_36Pk2MMvdZedxiAg7bvQ9H_NZecoZcju-sQKZiE7haq9Nos3g6njh9IpXivGJ1k8tRLeox7hXOeynffzcXz1Vnz5c4Zxw8LKUbLygni49sflKyFTMnQ8sgDw00fPsuhrznq37-2OLhmYe-tIg-TEV3T4VNdqchzeRSFIv_l7ZJcxeFxcEgdq9aXMx2yzVhSInFuk_W8fJSbhPKX9cewbr4BA_XUNMReowLVcnjB_19iCWnivkVk9sz-QRbjuVL2IMqZePWcRdN5ncXRJoYv4F-Z4FfXDCFuyCD4UAtiQfdch-S4KvRf99DwKrZrMIF28MDdRFdE3ZGDs3FXcPuN8eMLoKBrkyfkM3J89W1GNvrcCNHSNzhF8oPItU4Qno7-x52ZIOAjfdFcXTYLQYU7Xfr6GKaRByemPrkbkrJpdB8RQREt3rQRDNGRQ0jnbPn62PQugvss98JZn9D4ScNusbbgKMihj4MqfXE2mt7Ab9ewx5d01d-Mwf3D6mGz_ERBJgJo8b119bRXdNvgUDJC58NFd4chEOUF4mbyj2pZB9P7fx22yEvV7y6DNzuKvk02YQt7TwL7sdxH1PT63CYJx0tlVGGDvJhGKUQwOfDaXHFMjuuUlXa_klTJT5wEb78aAyh33rw0n9wpOakTIk2KgekbJAzVWCT0BfLrrOhKs3556_d--2mLmcLOONosBjSLokuvtyrTOX7btKRf6Zl5l3wtxsFaPgO6M3Qy9UR46AtK76XSFQd9kcDf_Qj1FyronJS_enQFWYn5Um97mDnYT9SJwMpDFS_FYBTKlsNhsVy11EW5kKuo6mTRlfebJa9CQv-NzbUajd7ulAcM4VNWYt-KbbhVZtUUUxgDvXJdlwRSYR5U8JwSze3sfatb5mbds-EAS-tT7grwrvTb4wRz20e9ARtBg6kC_x8QujHmFORJ97zrFlnnunPbsWgwWz8bfT9RMFy5xUE1KDCtnJqp-M3FoWwQc4sREIyCl7Q6JTq_slPe-Xwt9C5oquj4e_SoOuTAfqDPAmIG6rEXKSN7RP3KRjN5IA5Wpp2I0hgOJ6bT2qNAAUAAAAAAAAASAAAAAAAAAAKGkRhdGEtUHJvdG9jb2wEYW8QZnVuY3Rpb24GcmF3GkRhdGEtUHJvdG9jb2wEYW8OYW8tdHlwZQ5tZXNzYWdlBlNESwRhbwA2NTgz";
+
+    #[test]
+    fn test_verify_data_item() {
+        let item_bytes = base64_url::decode(ITEM_STR).unwrap();
+        let item = verify_data_item(item_bytes).unwrap();
+        assert_eq!(item.id(), "6oYAxVAnH8yKsZKpMgHSbRv7uVWey68PAqYuSXeZBbg");
+    }
+
+    #[test]
+    fn test_verify_data_item_tampered() {
+        let mut item_bytes = base64_url::decode(ITEM_STR).unwrap();
+        let last = item_bytes.len() - 1;
+        item_bytes[last] ^= 1;
+        assert!(verify_data_item(item_bytes).is_err());
+    }
+}
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use tokio::spawn;
 use tokio::time::{sleep, Duration};
 
-use crate::domain::core::dal::{Uploader, UploaderErrorType};
+use crate::domain::core::dal::{UploadCallback, Uploader, UploaderErrorType};
 use crate::domain::Log;
 
 pub struct UploaderClient {
@@ -52,7 +52,7 @@ impl UploaderClient {
 }
 
 impl Uploader for UploaderClient {
-    fn upload(&self, tx: Vec<u8>) -> Result<(), UploaderErrorType> {
+    fn upload(&self, tx: Vec<u8>, on_complete: UploadCallback) -> Result<(), UploaderErrorType> {
         let node_url_clone = self.node_url.clone();
         let tx_clone = tx.clone();
         let logger_clone = Arc::clone(&self.logger);
@@ -61,6 +61,7 @@ impl Uploader for UploaderClient {
             let client = Client::new();
             let mut delay = Duration::from_secs(1);
             let max_delay = Duration::from_secs(32);
+            let mut uploaded = false;
 
             for attempt in 0..100 {
                 let response = client
@@ -77,6 +78,7 @@ impl Uploader for UploaderClient {
                 match response {
                     Ok(resp) if resp.status().is_success() => {
                         logger_clone.log("Upload successful".to_string());
+                        uploaded = true;
                         break;
                     }
                     Ok(resp) => {
@@ -98,6 +100,13 @@ impl Uploader for UploaderClient {
                 // Double the delay for the next attempt, but don't exceed the max delay
                 delay = (delay * 2).min(max_delay);
             }
+
+            on_complete(match uploaded {
+                true => Ok(()),
+                false => Err(UploaderErrorType::UploadError(
+                    "Upload failed after 100 attempts".to_string(),
+                )),
+            });
         });
 
         Ok(())
@@ -1,13 +1,26 @@
 use std::env;
+use std::str::FromStr;
 
 use dotenv::dotenv;
+use reqwest::Url;
 
+use crate::domain::core::dal::StoreErrorType;
 use crate::domain::Config;
 
+/*
+  Where message and process bundles are written,
+  in the bundle column or as files under a directory.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum BundleStorage {
+    Db,
+    Filesystem(String),
+}
+
 #[derive(Debug, Clone)]
 pub struct AoConfig {
     pub database_url: String,
-    pub database_read_url: String,
+    pub database_read_url: Option<String>,
     pub su_wallet_path: String,
     pub graphql_url: String,
     pub arweave_url: String,
@@ -23,18 +36,40 @@ pub struct AoConfig {
     pub max_read_memory: usize,
     pub process_cache_size: usize,
     pub enable_process_assignment: bool,
+    pub db_ca_cert_path: Option<String>,
+    pub db_connect_retries: u32,
+    pub db_connect_retry_delay: u64,
+    pub bundle_storage: BundleStorage,
+    pub bundle_storage_threshold: usize,
+    pub validate_on_write: bool,
+    pub max_page_size: i64,
+    pub clamp_page_size: bool,
+    pub connection_max_lifetime: Option<u64>,
+    pub idle_timeout: Option<u64>,
+    pub db_schema: String,
+    pub slow_query_ms: Option<u64>,
+    pub verify_bundle_on_write: bool,
+    pub test_on_check_out: bool,
+    pub application_name: String,
+    pub max_bundle_bytes: usize,
+    pub process_cache_ttl: Option<u64>,
+    pub db_connection_timeout_ms: u64,
+    pub db_min_idle: Option<u32>,
+    pub serializable_retries: u32,
+    pub db_statement_timeout_ms: Option<u64>,
+    pub read_fallback_to_primary: bool,
 }
 
 impl AoConfig {
-    pub fn new(mode: Option<String>) -> Result<Self, env::VarError> {
+    pub fn new(mode: Option<String>) -> Result<Self, StoreErrorType> {
         dotenv().ok();
         let mode_out = match mode {
             Some(m) => m,
             None => env::var("MODE")?,
         };
         let database_read_url = match env::var("DATABASE_READ_URL") {
-            Ok(val) => val,
-            Err(_e) => env::var("DATABASE_URL")?,
+            Ok(val) if !val.is_empty() => Some(val),
+            _ => None,
         };
         let use_disk = match env::var("USE_DISK") {
             Ok(val) => val == "true",
@@ -45,15 +80,15 @@ impl AoConfig {
             false => "".to_string(),
         };
         let migration_batch_size = match env::var("MIGRATION_BATCH_SIZE") {
-            Ok(val) => val.parse().unwrap(),
+            Ok(val) => parse_var("MIGRATION_BATCH_SIZE", &val)?,
             Err(_e) => 1000,
         };
         let db_write_connections = match env::var("DB_WRITE_CONNECTIONS") {
-            Ok(val) => val.parse().unwrap(),
+            Ok(val) => parse_var("DB_WRITE_CONNECTIONS", &val)?,
             Err(_e) => 10,
         };
         let db_read_connections = match env::var("DB_READ_CONNECTIONS") {
-            Ok(val) => val.parse().unwrap(),
+            Ok(val) => parse_var("DB_READ_CONNECTIONS", &val)?,
             Err(_e) => 10,
         };
         let graphql_url = match env::var("GRAPHQL_URL") {
@@ -69,17 +104,105 @@ impl AoConfig {
             Err(_e) => false,
         };
         let max_read_memory = match env::var("MAX_READ_MEMORY") {
-            Ok(val) => val.parse().unwrap(),
+            Ok(val) => parse_var("MAX_READ_MEMORY", &val)?,
             Err(_e) => 1_073_741_824,
         };
         let process_cache_size = match env::var("PROCESS_CACHE_SIZE") {
-            Ok(val) => val.parse().unwrap(),
+            Ok(val) => parse_var("PROCESS_CACHE_SIZE", &val)?,
             Err(_e) => 20000,
         };
         let enable_process_assignment = match env::var("ENABLE_PROCESS_ASSIGNMENT") {
             Ok(val) => val == "true",
             Err(_e) => false,
         };
+        let db_ca_cert_path = match env::var("DB_CA_CERT_PATH") {
+            Ok(val) if !val.is_empty() => Some(val),
+            _ => None,
+        };
+        let db_connect_retries = match env::var("DB_CONNECT_RETRIES") {
+            Ok(val) => parse_var("DB_CONNECT_RETRIES", &val)?,
+            Err(_e) => 10,
+        };
+        let db_connect_retry_delay = match env::var("DB_CONNECT_RETRY_DELAY") {
+            Ok(val) => parse_var("DB_CONNECT_RETRY_DELAY", &val)?,
+            Err(_e) => 500,
+        };
+        let bundle_storage = match env::var("BUNDLE_STORAGE_PATH") {
+            Ok(val) if !val.is_empty() => BundleStorage::Filesystem(val),
+            _ => BundleStorage::Db,
+        };
+        let bundle_storage_threshold = match env::var("BUNDLE_STORAGE_THRESHOLD") {
+            Ok(val) => parse_var("BUNDLE_STORAGE_THRESHOLD", &val)?,
+            Err(_e) => 0,
+        };
+        let validate_on_write = match env::var("VALIDATE_ON_WRITE") {
+            Ok(val) => val == "true",
+            Err(_e) => false,
+        };
+        let max_page_size = match env::var("MAX_PAGE_SIZE") {
+            Ok(val) => parse_var("MAX_PAGE_SIZE", &val)?,
+            Err(_e) => 1000,
+        };
+        let clamp_page_size = match env::var("CLAMP_PAGE_SIZE") {
+            Ok(val) => val == "true",
+            Err(_e) => false,
+        };
+        let connection_max_lifetime = match env::var("DB_CONNECTION_MAX_LIFETIME") {
+            Ok(val) => Some(parse_var("DB_CONNECTION_MAX_LIFETIME", &val)?),
+            Err(_e) => None,
+        };
+        let idle_timeout = match env::var("DB_IDLE_TIMEOUT") {
+            Ok(val) => Some(parse_var("DB_IDLE_TIMEOUT", &val)?),
+            Err(_e) => None,
+        };
+        let db_schema = match env::var("DB_SCHEMA") {
+            Ok(val) if !val.is_empty() => val,
+            _ => "public".to_string(),
+        };
+        let slow_query_ms = match env::var("SLOW_QUERY_MS") {
+            Ok(val) => Some(parse_var("SLOW_QUERY_MS", &val)?),
+            Err(_e) => None,
+        };
+        let verify_bundle_on_write = match env::var("VERIFY_BUNDLE_ON_WRITE") {
+            Ok(val) => val == "true",
+            Err(_e) => false,
+        };
+        let test_on_check_out = match env::var("DB_TEST_ON_CHECK_OUT") {
+            Ok(val) => val != "false",
+            Err(_e) => true,
+        };
+        let application_name = match env::var("DB_APPLICATION_NAME") {
+            Ok(val) if !val.is_empty() => val,
+            _ => "ao-su".to_string(),
+        };
+        let max_bundle_bytes = match env::var("MAX_BUNDLE_BYTES") {
+            Ok(val) => parse_var("MAX_BUNDLE_BYTES", &val)?,
+            Err(_e) => 0,
+        };
+        let process_cache_ttl = match env::var("PROCESS_CACHE_TTL") {
+            Ok(val) => Some(parse_var("PROCESS_CACHE_TTL", &val)?),
+            Err(_e) => None,
+        };
+        let db_connection_timeout_ms = match env::var("DB_CONNECTION_TIMEOUT_MS") {
+            Ok(val) => parse_var("DB_CONNECTION_TIMEOUT_MS", &val)?,
+            Err(_e) => 30_000,
+        };
+        let db_min_idle = match env::var("DB_MIN_IDLE") {
+            Ok(val) => Some(parse_var("DB_MIN_IDLE", &val)?),
+            Err(_e) => None,
+        };
+        let serializable_retries = match env::var("SERIALIZABLE_RETRIES") {
+            Ok(val) => parse_var("SERIALIZABLE_RETRIES", &val)?,
+            Err(_e) => 3,
+        };
+        let db_statement_timeout_ms = match env::var("DB_STATEMENT_TIMEOUT_MS") {
+            Ok(val) => Some(parse_var("DB_STATEMENT_TIMEOUT_MS", &val)?),
+            Err(_e) => None,
+        };
+        let read_fallback_to_primary = match env::var("READ_FALLBACK_TO_PRIMARY") {
+            Ok(val) => val == "true",
+            Err(_e) => false,
+        };
         Ok(AoConfig {
             database_url: env::var("DATABASE_URL")?,
             database_read_url,
@@ -97,9 +220,93 @@ impl AoConfig {
             enable_metrics,
             max_read_memory,
             process_cache_size,
-            enable_process_assignment
+            enable_process_assignment,
+            db_ca_cert_path,
+            db_connect_retries,
+            db_connect_retry_delay,
+            bundle_storage,
+            bundle_storage_threshold,
+            validate_on_write,
+            max_page_size,
+            clamp_page_size,
+            connection_max_lifetime,
+            idle_timeout,
+            db_schema,
+            slow_query_ms,
+            verify_bundle_on_write,
+            test_on_check_out,
+            application_name,
+            max_bundle_bytes,
+            process_cache_ttl,
+            db_connection_timeout_ms,
+            db_min_idle,
+            serializable_retries,
+            db_statement_timeout_ms,
+            read_fallback_to_primary,
         })
     }
+
+    /*
+      Catch settings the store can not work with before
+      a pool is built, where they would otherwise show up
+      as a connection error or a panic inside r2d2. The
+      urls are never echoed as they carry the password.
+    */
+    pub fn validate(&self) -> Result<(), StoreErrorType> {
+        check_database_url("DATABASE_URL", &self.database_url)?;
+        if let Some(database_read_url) = &self.database_read_url {
+            check_database_url("DATABASE_READ_URL", database_read_url)?;
+        }
+
+        let at_least_one = [
+            ("DB_WRITE_CONNECTIONS", self.db_write_connections as i64),
+            ("DB_READ_CONNECTIONS", self.db_read_connections as i64),
+            ("PROCESS_CACHE_SIZE", self.process_cache_size as i64),
+            ("MAX_PAGE_SIZE", self.max_page_size),
+            (
+                "DB_CONNECTION_TIMEOUT_MS",
+                self.db_connection_timeout_ms as i64,
+            ),
+        ];
+        for (name, value) in at_least_one {
+            if value < 1 {
+                return Err(StoreErrorType::ConfigError(format!(
+                    "{} must be at least 1, got {}",
+                    name, value
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/*
+  Numeric settings that do not parse are a ConfigError
+  naming the variable, rather than a panic at startup.
+*/
+fn parse_var<T: FromStr>(name: &str, val: &str) -> Result<T, StoreErrorType>
+where
+    T::Err: std::fmt::Display,
+{
+    val.parse().map_err(|e| {
+        StoreErrorType::ConfigError(format!("{} is not a valid number {:?}: {}", name, val, e))
+    })
+}
+
+fn check_database_url(name: &str, database_url: &str) -> Result<(), StoreErrorType> {
+    match Url::parse(database_url) {
+        Ok(url) if url.scheme() == "postgres" || url.scheme() == "postgresql" => Ok(()),
+        Ok(url) => Err(StoreErrorType::ConfigError(format!(
+            "{} must be a postgres url, got scheme {}",
+            name,
+            url.scheme()
+        ))),
+        Err(e) => Err(StoreErrorType::ConfigError(format!(
+            "{} is not a valid url: {}",
+            name, e
+        ))),
+    }
 }
 
 impl Config for AoConfig {
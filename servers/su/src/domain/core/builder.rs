@@ -299,6 +299,9 @@ mod tests {
         fn log(&self, message: String) {
             println!("{}", message)
         }
+        fn warn(&self, message: String) {
+            println!("{}", message);
+        }
         fn error(&self, message: String) {
             println!("{}", message);
         }
@@ -372,6 +372,14 @@ impl DataItem {
         !self.signature.is_empty() && self.signature_type != SignerMap::None
     }
 
+    pub fn is_arweave_signed(&self) -> bool {
+        self.is_signed() && self.signature_type == SignerMap::Arweave
+    }
+
+    pub fn raw_owner(&self) -> &[u8] {
+        &self.owner
+    }
+
     fn from_info_bytes(buffer: &[u8]) -> Result<(Self, usize), ByteErrorType> {
         if buffer.len() < 2 {
             return Err(ByteErrorType::ByteError(
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use serde::Deserialize;
 
+pub use super::bytes::DataItem;
 pub use super::json::{JsonErrorType, Message, PaginatedMessages, Process};
 pub use super::router::{ProcessScheduler, Scheduler};
 
@@ -47,6 +48,7 @@ pub trait Signer: Send + Sync {
 
 pub trait Log: Send + Sync {
     fn log(&self, message: String);
+    fn warn(&self, message: String);
     fn error(&self, message: String);
 }
 
@@ -74,8 +76,14 @@ impl From<UploaderErrorType> for String {
     }
 }
 
+/*
+  Called once with the outcome of an upload, which the
+  uploader may only know after upload has returned.
+*/
+pub type UploadCallback = Box<dyn FnOnce(Result<(), UploaderErrorType>) + Send>;
+
 pub trait Uploader: Send + Sync {
-    fn upload(&self, tx: Vec<u8>) -> Result<(), UploaderErrorType>;
+    fn upload(&self, tx: Vec<u8>, on_complete: UploadCallback) -> Result<(), UploaderErrorType>;
 }
 
 #[derive(Debug)]
@@ -86,6 +94,21 @@ pub enum StoreErrorType {
     EnvVarError(String),
     IntError(String),
     MessageExists(String),
+    ShuttingDown(String),
+    ValidationError(Vec<String>),
+    InvalidBundle(String),
+    BundleTooLarge {
+        size: usize,
+        limit: usize,
+    },
+    SerializationFailure(String),
+    ConfigError(String),
+    PoolTimeout {
+        waited_ms: u64,
+        in_use: u32,
+        max_size: u32,
+        reason: String,
+    },
 }
 
 #[async_trait]
@@ -115,11 +138,21 @@ pub trait DataStore: Send + Sync {
         process_id_in: &str,
     ) -> Result<ProcessScheduler, StoreErrorType>;
     fn save_scheduler(&self, scheduler: &Scheduler) -> Result<String, StoreErrorType>;
-    fn update_scheduler(&self, scheduler: &Scheduler) -> Result<String, StoreErrorType>;
+    fn update_scheduler(
+        &self,
+        row_id: i32,
+        scheduler: &Scheduler,
+    ) -> Result<Scheduler, StoreErrorType>;
     fn get_scheduler(&self, row_id_in: &i32) -> Result<Scheduler, StoreErrorType>;
     fn get_scheduler_by_url(&self, url_in: &String) -> Result<Scheduler, StoreErrorType>;
     fn get_all_schedulers(&self) -> Result<Vec<Scheduler>, StoreErrorType>;
     fn check_existing_message(&self, message_id: &String) -> Result<(), StoreErrorType>;
+    fn mark_bundle_uploaded(
+        &self,
+        assignment_id: &str,
+        tx_id: &str,
+    ) -> Result<String, StoreErrorType>;
+    fn mark_bundle_upload_failed(&self, assignment_id: &str) -> Result<String, StoreErrorType>;
 }
 
 pub trait CoreMetrics: Send + Sync {
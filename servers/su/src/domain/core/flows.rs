@@ -7,10 +7,13 @@ use serde_json::json;
 use simd_json::to_string as simd_to_string;
 
 use super::builder::Builder;
+use super::bytes::DataItem;
 use super::json::{Message, Process};
 use super::scheduler;
 
-use super::dal::{Config, CoreMetrics, DataStore, Gateway, Log, Signer, Uploader, Wallet};
+use super::dal::{
+    Config, CoreMetrics, DataStore, Gateway, Log, Signer, UploadCallback, Uploader, Wallet,
+};
 
 pub struct Deps {
     pub data_store: Arc<dyn DataStore>,
@@ -41,8 +44,41 @@ pub fn init_builder(deps: &Arc<Deps>) -> Result<Builder, String> {
     return Ok(builder);
 }
 
-async fn upload(deps: &Arc<Deps>, build_result: Vec<u8>) -> Result<String, String> {
-    let uploaded_tx = &deps.uploader.upload(build_result)?;
+/*
+  Upload a bundle in the background. For a message the
+  outcome is recorded on its assignment's row once the
+  uploader is done, so get_pending_uploads only returns
+  bundles that never made it. Processes have no upload
+  status.
+*/
+async fn upload(
+    deps: &Arc<Deps>,
+    build_result: Vec<u8>,
+    assignment_id: Option<String>,
+) -> Result<String, String> {
+    let on_complete: UploadCallback = match assignment_id {
+        Some(assignment_id) => {
+            let tx_id = DataItem::from_bytes(build_result.clone())
+                .map_err(|e| format!("{:?}", e))?
+                .id();
+            let data_store = deps.data_store.clone();
+            let logger = deps.logger.clone();
+            Box::new(move |result| {
+                let marked = match result {
+                    Ok(()) => data_store.mark_bundle_uploaded(&assignment_id, &tx_id),
+                    Err(_) => data_store.mark_bundle_upload_failed(&assignment_id),
+                };
+                if let Err(e) = marked {
+                    logger.error(format!(
+                        "Failed to record upload status for {}: {:?}",
+                        assignment_id, e
+                    ));
+                }
+            })
+        }
+        None => Box::new(|_| ()),
+    };
+    let uploaded_tx = &deps.uploader.upload(build_result, on_complete)?;
     let result = match serde_json::to_string(&uploaded_tx) {
         Ok(r) => r,
         Err(e) => return Err(format!("{:?}", e)),
@@ -176,7 +212,12 @@ pub async fn write_item(
         );
         drop(schedule_info);
 
-        upload(&deps, build_result.binary.to_vec()).await?;
+        upload(
+            &deps,
+            build_result.binary.to_vec(),
+            Some(message.assignment_id()?),
+        )
+        .await?;
         return id_res(&deps, return_aid, start_top_level);
     }
 
@@ -234,7 +275,7 @@ pub async fn write_item(
                     .commit(&mut *schedule_info, &next_schedule_info, did, aid);
                 drop(schedule_info);
 
-                upload(&deps, build_result.binary.to_vec()).await?;
+                upload(&deps, build_result.binary.to_vec(), None).await?;
                 return id_res(&deps, process.process.process_id.clone(), start_top_level);
             } else {
                 let build_result = builder.build_process(input, &next_schedule_info).await?;
@@ -251,7 +292,7 @@ pub async fn write_item(
                 */
                 drop(schedule_info);
 
-                upload(&deps, build_result.binary.to_vec()).await?;
+                upload(&deps, build_result.binary.to_vec(), None).await?;
                 return id_res(&deps, process.process.process_id.clone(), start_top_level);
             }
         } else if type_tag.value == "Message" {
@@ -282,7 +323,12 @@ pub async fn write_item(
                 .commit(&mut *schedule_info, &next_schedule_info, dtarget, aid);
             drop(schedule_info);
 
-            upload(&deps, build_result.binary.to_vec()).await?;
+            upload(
+                &deps,
+                build_result.binary.to_vec(),
+                Some(message.assignment_id()?),
+            )
+            .await?;
             return id_res(&deps, message.message_id()?, start_top_level);
         } else {
             return Err("Type tag not present".to_string());
@@ -627,6 +627,19 @@ impl Message {
         Ok(timestamp_tag.value.parse::<i64>()?)
     }
 
+    /*
+      The Timestamp tag the sender put on the message
+      itself, which can differ from the time it was
+      assigned. The sender controls it, so a missing or
+      non numeric tag is None rather than an error.
+    */
+    pub fn message_timestamp(&self) -> Option<i64> {
+        self.message
+            .as_ref()
+            .and_then(|message| message.tags.iter().find(|tag| tag.name == "Timestamp"))
+            .and_then(|tag| tag.value.parse::<i64>().ok())
+    }
+
     pub fn hash_chain(&self) -> Result<String, JsonErrorType> {
         let hash_chain_tag = self
             .assignment
@@ -660,6 +673,16 @@ impl Message {
         Ok(message_tag.value.clone())
     }
 
+    /*
+      The address that signed the message itself, None
+      for an assignment of a message from elsewhere.
+    */
+    pub fn owner_address(&self) -> Option<String> {
+        self.message
+            .as_ref()
+            .map(|message| message.owner.address.clone())
+    }
+
     pub fn assignment_id(&self) -> Result<String, JsonErrorType> {
         let assignment_id = self.assignment.id.clone();
         Ok(assignment_id)
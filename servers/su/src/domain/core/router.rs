@@ -19,6 +19,27 @@ pub struct Scheduler {
     pub url: String,
     pub process_count: i32,
     pub no_route: Option<bool>,
+    pub capacity: i32,
+    pub enabled: bool,
+}
+
+impl Scheduler {
+    /*
+      How full a scheduler is relative to its capacity,
+      new processes go to the scheduler with the lowest.
+    */
+    pub fn load(&self) -> f64 {
+        self.process_count as f64 / self.capacity.max(1) as f64
+    }
+
+    /*
+      Whether new processes may be assigned here. A
+      disabled scheduler is being drained, it keeps the
+      processes it already has.
+    */
+    pub fn is_routable(&self) -> bool {
+        self.enabled && !self.no_route.unwrap_or(false)
+    }
 }
 
 pub struct ProcessScheduler {
@@ -31,6 +52,7 @@ pub struct ProcessScheduler {
 struct SchedulerEntry {
     url: String,
     no_route: Option<bool>,
+    capacity: Option<i32>,
 }
 
 /*
@@ -61,6 +83,8 @@ pub async fn init_schedulers(deps: Arc<Deps>) -> Result<String, String> {
                 url: entry.url.clone(),
                 process_count: 0,
                 no_route: entry.no_route,
+                capacity: entry.capacity.unwrap_or(1),
+                enabled: true,
             };
             deps.data_store.save_scheduler(&scheduler)?;
             deps.logger
@@ -73,7 +97,13 @@ pub async fn init_schedulers(deps: Arc<Deps>) -> Result<String, String> {
         */
         let mut sched = deps.data_store.get_scheduler_by_url(&entry.url)?;
         sched.no_route = entry.no_route;
-        deps.data_store.update_scheduler(&sched)?;
+        if let Some(capacity) = entry.capacity {
+            sched.capacity = capacity;
+        }
+        let sched_row_id = sched
+            .row_id
+            .ok_or_else(|| "Missing id on scheduler".to_string())?;
+        deps.data_store.update_scheduler(sched_row_id, &sched)?;
     }
 
     Ok("schedulers initialized".to_string())
@@ -170,13 +200,13 @@ pub async fn redirect_data_item(
                 .data_store
                 .get_all_schedulers()?
                 .into_iter()
-                .filter(|scheduler| scheduler.no_route.unwrap_or(false) == false)
+                .filter(|scheduler| scheduler.is_routable())
                 .collect::<Vec<_>>();
 
-            if let Some(min_scheduler) = schedulers.iter_mut().min_by_key(|s| s.process_count) {
-                min_scheduler.process_count += 1;
-                deps.data_store.update_scheduler(min_scheduler)?;
-
+            if let Some(min_scheduler) = schedulers
+                .iter_mut()
+                .min_by(|a, b| a.load().total_cmp(&b.load()))
+            {
                 let scheduler_row_id = if let Some(min_scheduler_row_id) = min_scheduler.row_id {
                     min_scheduler_row_id
                 } else {
@@ -187,6 +217,10 @@ pub async fn redirect_data_item(
                     return Err("Missing id on scheduler".to_string());
                 };
 
+                min_scheduler.process_count += 1;
+                deps.data_store
+                    .update_scheduler(scheduler_row_id, min_scheduler)?;
+
                 let process_scheduler = ProcessScheduler {
                     row_id: None,
                     scheduler_row_id: scheduler_row_id,
@@ -1,7 +1,7 @@
 use std::sync::{Arc, Once};
 
 use env_logger::Env;
-use log::{error, info};
+use log::{error, info, warn};
 
 use crate::domain::Log;
 
@@ -23,6 +23,10 @@ impl Log for SuLog {
         info!("{}", message);
     }
 
+    fn warn(&self, message: String) {
+        warn!("{}", message);
+    }
+
     fn error(&self, message: String) {
         error!("{}", message);
     }
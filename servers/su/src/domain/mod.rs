@@ -15,16 +15,34 @@ use config::AoConfig;
 use core::dal::{Config, Gateway, Log};
 use logger::SuLog;
 
+pub use clients::async_store::AsyncStoreClient;
 pub use clients::metrics::PromMetrics;
+pub use clients::store::StoreClient;
 pub use core::flows;
 pub use core::router;
 pub use flows::Deps;
-pub use store::migrate_to_disk;
+pub use store::{backfill_message_owners, diff_manifests, migrate_to_disk};
 
 pub async fn init_deps(mode: Option<String>, metrics_registry: prometheus::Registry) -> Arc<Deps> {
     let logger: Arc<dyn Log> = SuLog::init();
 
-    let data_store = Arc::new(store::StoreClient::new().expect("Failed to create StoreClient"));
+    /*
+      new_lazy sleeps between its connection retries, so
+      it runs on a blocking thread to keep the runtime
+      free. Running out of retries ends the process with
+      the error logged rather than a panic.
+    */
+    let data_store = match spawn_blocking(store::StoreClient::new_lazy).await {
+        Ok(Ok(data_store)) => Arc::new(data_store),
+        Ok(Err(e)) => {
+            logger.error(format!("Failed to create StoreClient: {:?}", e));
+            std::process::exit(1);
+        }
+        Err(e) => {
+            logger.error(format!("Failed to create StoreClient: {:?}", e));
+            std::process::exit(1);
+        }
+    };
     let d_clone = data_store.clone();
 
     match data_store.run_migrations() {
@@ -74,6 +92,7 @@ pub async fn init_deps(mode: Option<String>, metrics_registry: prometheus::Regis
     let metrics = Arc::new(PromMetrics::new(
         AoConfig::new(mode).expect("Failed to read configuration"),
         metrics_registry,
+        data_store.duplicates_ignored_counter(),
     ));
 
     Arc::new(Deps {